@@ -0,0 +1,39 @@
+//! Aarch64 CPU feature bit positions shared by every OS-specific detection
+//! backend.
+
+/// Aarch64 CPU Feature enum. Each variant denotes a position in the feature
+/// bitset that `Initializer` understands.
+///
+/// This table is OS-agnostic: both the Linux `AtHwcap` backend and the
+/// Windows `IsProcessorFeaturePresent`-based backend set bits from this one
+/// enum, so a given bit means the same feature regardless of which backend
+/// produced it.
+#[allow(non_camel_case_types)]
+#[repr(u32)]
+pub(crate) enum Feature {
+    fp = 0,
+    asimd = 1,
+    aes = 3,
+    pmull = 4,
+    sha1 = 5,
+    sha2 = 6,
+    crc32 = 7,
+    atomics = 8,
+    fphp = 9,
+    asimdhp = 10,
+    asimdrdm = 12,
+    jscvt = 13,
+    fcma = 14,
+    lrcpc = 15,
+    dcpop = 16,
+    sha3 = 17,
+    sm3 = 18,
+    sm4 = 19,
+    asimddp = 20,
+    sha512 = 21,
+    sve = 22,
+    asimdfhm = 23,
+    sve2 = 24,
+    i8mm = 25,
+    bf16 = 26,
+}
@@ -0,0 +1,83 @@
+//! Caches the run-time feature detection result.
+//!
+//! Feature detection is relatively expensive (it may require parsing the ELF
+//! auxiliary vector, `/proc/cpuinfo`, or making a few syscalls), so the
+//! result is computed once and cached in a single `AtomicU64` bitset that
+//! subsequent calls to `test` can check cheaply.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// The bit position of each feature is architecture-specific and is assigned
+/// by each architecture's `Feature` enum; this cache is deliberately kept
+/// architecture-agnostic so it can be shared across all of them.
+static CACHE: Cache = Cache::uninitialized();
+
+/// A bitset of the features that have been detected as being present.
+///
+/// This is the value that OS-specific and architecture-specific detection
+/// code builds up before handing it to `Cache::initialize`.
+#[derive(Copy, Clone)]
+pub(crate) struct Initializer(u64);
+
+impl Default for Initializer {
+    fn default() -> Self {
+        Initializer(0)
+    }
+}
+
+impl Initializer {
+    /// Sets the `bit` of the bitset.
+    pub(crate) fn set(&mut self, bit: u32) {
+        debug_assert!(
+            (bit as usize) < 8 * core::mem::size_of::<u64>(),
+            "bit index out-of-bounds"
+        );
+        self.0 |= 1 << bit;
+    }
+
+    /// Tests the `bit` of the bitset.
+    pub(crate) fn test(&self, bit: u32) -> bool {
+        debug_assert!(
+            (bit as usize) < 8 * core::mem::size_of::<u64>(),
+            "bit index out-of-bounds"
+        );
+        self.0 & (1 << bit) != 0
+    }
+}
+
+/// Performs run-time feature detection and, if it has not been done
+/// already, stores the result in `CACHE`.
+///
+/// `f` is called while the cache is still uninitialized and is given the
+/// chance to build up an `Initializer` from whatever OS-specific mechanism
+/// is available. Threads that race on the first call may each call `f`;
+/// each stores its result, and later calls reuse the stored value.
+pub(crate) fn test<F>(bit: u32, f: F) -> bool
+where
+    F: FnOnce() -> Initializer,
+{
+    CACHE.test(bit, f)
+}
+
+/// Lazily-initialized feature bitset shared by all architectures.
+struct Cache(AtomicU64);
+
+impl Cache {
+    const UNINITIALIZED: u64 = u64::max_value();
+
+    const fn uninitialized() -> Self {
+        Cache(AtomicU64::new(Cache::UNINITIALIZED))
+    }
+
+    fn test<F>(&self, bit: u32, f: F) -> bool
+    where
+        F: FnOnce() -> Initializer,
+    {
+        let mut value = self.0.load(Ordering::Relaxed);
+        if value == Cache::UNINITIALIZED {
+            value = f().0;
+            self.0.store(value, Ordering::Relaxed);
+        }
+        Initializer(value).test(bit)
+    }
+}
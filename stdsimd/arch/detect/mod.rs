@@ -0,0 +1,44 @@
+//! Run-time feature detection.
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64;
+mod cache;
+mod os;
+
+/// Detects the CPU features available on Aarch64 and folds them into a
+/// `cache::Initializer`.
+///
+/// The public entry point is the same regardless of OS: callers don't need
+/// to know whether the result came from the auxiliary vector (Linux,
+/// FreeBSD) or from a native Win32 API (Windows).
+#[cfg(target_arch = "aarch64")]
+pub(crate) fn detect_features() -> cache::Initializer {
+    cfg_if! {
+        if #[cfg(all(target_os = "windows", target_arch = "aarch64"))] {
+            os::detect_features()
+        } else if #[cfg(any(target_os = "linux", target_os = "freebsd"))] {
+            os::auxv()
+                .map(|auxv| os::AtHwcap::from(auxv).into_initializer())
+                .unwrap_or_default()
+        } else {
+            cache::Initializer::default()
+        }
+    }
+}
+
+/// Detects the CPU features available on RISC-V and folds them into a
+/// `cache::Initializer`.
+///
+/// The public entry point is the same regardless of OS: callers don't need
+/// to know whether the result came from the auxiliary vector or the
+/// `riscv_hwprobe` syscall (Linux) or from some other platform mechanism.
+#[cfg(target_arch = "riscv64")]
+pub(crate) fn detect_features() -> cache::Initializer {
+    cfg_if! {
+        if #[cfg(target_os = "linux")] {
+            os::detect_features()
+        } else {
+            cache::Initializer::default()
+        }
+    }
+}
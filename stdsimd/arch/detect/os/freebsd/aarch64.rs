@@ -0,0 +1,144 @@
+//! Run-time feature detection for Aarch64 on FreeBSD.
+
+use super::auxvec::AuxVec;
+use super::super::aarch64::Feature;
+use super::super::cache::Initializer;
+
+/// A decoded view of the `HWCAP`/`HWCAP2` bitfields of the auxiliary vector,
+/// with one named boolean per bit instead of the raw integers.
+///
+/// The bit assignments match the kernel's `asm/hwcap.h`, which FreeBSD
+/// mirrors, so this is decoded the same way as Linux's `AtHwcap`.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct AtHwcap {
+    fp: bool,
+    asimd: bool,
+    aes: bool,
+    pmull: bool,
+    sha1: bool,
+    sha2: bool,
+    crc32: bool,
+    atomics: bool,
+    fphp: bool,
+    asimdhp: bool,
+    asimdrdm: bool,
+    jscvt: bool,
+    fcma: bool,
+    lrcpc: bool,
+    dcpop: bool,
+    sha3: bool,
+    sm3: bool,
+    sm4: bool,
+    asimddp: bool,
+    sha512: bool,
+    sve: bool,
+    asimdfhm: bool,
+
+    // HWCAP2 bits:
+    sve2: bool,
+    i8mm: bool,
+    bf16: bool,
+}
+
+impl From<AuxVec> for AtHwcap {
+    /// Reads the bitfields of `AuxVec` into a named `AtHwcap`.
+    fn from(auxv: AuxVec) -> Self {
+        AtHwcap {
+            fp: bit(auxv.hwcap, 0),
+            asimd: bit(auxv.hwcap, 1),
+            aes: bit(auxv.hwcap, 3),
+            pmull: bit(auxv.hwcap, 4),
+            sha1: bit(auxv.hwcap, 5),
+            sha2: bit(auxv.hwcap, 6),
+            crc32: bit(auxv.hwcap, 7),
+            atomics: bit(auxv.hwcap, 8),
+            fphp: bit(auxv.hwcap, 9),
+            asimdhp: bit(auxv.hwcap, 10),
+            asimdrdm: bit(auxv.hwcap, 12),
+            jscvt: bit(auxv.hwcap, 13),
+            fcma: bit(auxv.hwcap, 14),
+            lrcpc: bit(auxv.hwcap, 15),
+            dcpop: bit(auxv.hwcap, 16),
+            sha3: bit(auxv.hwcap, 17),
+            sm3: bit(auxv.hwcap, 18),
+            sm4: bit(auxv.hwcap, 19),
+            asimddp: bit(auxv.hwcap, 20),
+            sha512: bit(auxv.hwcap, 21),
+            sve: bit(auxv.hwcap, 22),
+            asimdfhm: bit(auxv.hwcap, 23),
+
+            // HWCAP2:
+            sve2: bit(auxv.hwcap2, 1),
+            i8mm: bit(auxv.hwcap2, 13),
+            bf16: bit(auxv.hwcap2, 14),
+        }
+    }
+}
+
+impl AtHwcap {
+    /// Folds the decoded bits into the shared `cache::Initializer`.
+    pub(crate) fn into_initializer(self) -> Initializer {
+        let mut value = Initializer::default();
+        {
+            let mut enable_feature = |f: Feature, enabled: bool| {
+                if enabled {
+                    value.set(f as u32);
+                }
+            };
+            enable_feature(Feature::fp, self.fp);
+            enable_feature(Feature::asimd, self.asimd);
+            enable_feature(Feature::aes, self.aes);
+            enable_feature(Feature::pmull, self.pmull);
+            enable_feature(Feature::sha1, self.sha1);
+            enable_feature(Feature::sha2, self.sha2);
+            enable_feature(Feature::crc32, self.crc32);
+            enable_feature(Feature::atomics, self.atomics);
+            enable_feature(Feature::fphp, self.fphp);
+            enable_feature(Feature::asimdhp, self.asimdhp);
+            enable_feature(Feature::asimdrdm, self.asimdrdm);
+            enable_feature(Feature::jscvt, self.jscvt);
+            enable_feature(Feature::fcma, self.fcma);
+            enable_feature(Feature::lrcpc, self.lrcpc);
+            enable_feature(Feature::dcpop, self.dcpop);
+            enable_feature(Feature::sha3, self.sha3);
+            enable_feature(Feature::sm3, self.sm3);
+            enable_feature(Feature::sm4, self.sm4);
+            enable_feature(Feature::asimddp, self.asimddp);
+            enable_feature(Feature::sha512, self.sha512);
+            enable_feature(Feature::sve, self.sve);
+            enable_feature(Feature::asimdfhm, self.asimdfhm);
+            enable_feature(Feature::sve2, self.sve2);
+            enable_feature(Feature::i8mm, self.i8mm);
+            enable_feature(Feature::bf16, self.bf16);
+        }
+        value
+    }
+}
+
+/// Tests whether bit `idx` of `x` is set.
+fn bit(x: usize, idx: u32) -> bool {
+    x & (1 << idx) != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atomics_bit_decoded() {
+        let hwcap = AtHwcap::from(AuxVec { hwcap: 1 << 8, hwcap2: 0 });
+        assert!(hwcap.atomics);
+        assert!(!hwcap.aes);
+    }
+
+    #[test]
+    fn hwcap2_bits_decoded() {
+        let hwcap = AtHwcap::from(AuxVec {
+            hwcap: 0,
+            hwcap2: (1 << 1) | (1 << 13) | (1 << 14),
+        });
+        assert!(hwcap.sve2);
+        assert!(hwcap.i8mm);
+        assert!(hwcap.bf16);
+    }
+}
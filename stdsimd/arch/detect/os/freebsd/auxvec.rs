@@ -0,0 +1,113 @@
+//! Parses ELF auxiliary vectors.
+
+/// Key to access the CPU Hardware capabilities bitfield.
+pub const AT_HWCAP: usize = 25;
+/// Key to access the CPU Hardware capabilities 2 bitfield.
+#[cfg(any(target_arch = "arm", target_arch = "aarch64", target_arch = "powerpc64"))]
+pub const AT_HWCAP2: usize = 26;
+
+/// Cache HWCAP bitfields of the ELF Auxiliary Vector.
+///
+/// If an entry cannot be read all the bits in the bitfield are set to zero.
+/// This should be interpreted as all the features being disabled.
+#[derive(Debug, Copy, Clone)]
+pub struct AuxVec {
+    pub hwcap: usize,
+    #[cfg(any(target_arch = "arm", target_arch = "aarch64", target_arch = "powerpc64"))]
+    pub hwcap2: usize,
+}
+
+/// ELF Auxiliary Vector
+///
+/// The auxiliary vector is a memory region in a running ELF program's stack
+/// composed of (key: usize, value: usize) pairs.
+///
+/// FreeBSD does not expose `/proc/self/auxv`, so unlike the Linux
+/// implementation there is no file to fall back to: `elf_aux_info` is the
+/// only source of truth here, and failing to read `AT_HWCAP` is a hard error.
+///
+/// For more information about the auxiliary vector check the
+/// [`elf_aux_info` documentation][elf_aux_info_docs].
+///
+/// [elf_aux_info_docs]: https://www.freebsd.org/cgi/man.cgi?query=elf_aux_info
+pub fn auxv() -> Result<AuxVec, ()> {
+    // Targets with only AT_HWCAP:
+    #[cfg(any(target_arch = "mips", target_arch = "mips64"))]
+    {
+        if let Ok(hwcap) = elf_aux_info(AT_HWCAP) {
+            if hwcap != 0 {
+                return Ok(AuxVec { hwcap });
+            }
+        }
+    }
+
+    // Targets with AT_HWCAP and AT_HWCAP2:
+    #[cfg(any(target_arch = "arm", target_arch = "powerpc64"))]
+    {
+        if let (Ok(hwcap), Ok(hwcap2)) =
+            (elf_aux_info(AT_HWCAP), elf_aux_info(AT_HWCAP2))
+        {
+            if hwcap != 0 && hwcap2 != 0 {
+                return Ok(AuxVec { hwcap, hwcap2 });
+            }
+        }
+    }
+
+    // Aarch64 also has AT_HWCAP2, but it was only added once SVE2 and
+    // friends needed a second word: treat it as optional and fall back to
+    // zero (all HWCAP2 features disabled) rather than failing the whole
+    // read when it is absent, keeping this `AuxVec` the same shape as
+    // Linux's.
+    #[cfg(target_arch = "aarch64")]
+    {
+        if let Ok(hwcap) = elf_aux_info(AT_HWCAP) {
+            if hwcap != 0 {
+                let hwcap2 = elf_aux_info(AT_HWCAP2).unwrap_or(0);
+                return Ok(AuxVec { hwcap, hwcap2 });
+            }
+        }
+    }
+
+    Err(())
+}
+
+/// Tries to read the `key` from the auxiliary vector by calling FreeBSD's
+/// `elf_aux_info` libc function. If the function is not available, or it
+/// reports an error, this function returns `Err`.
+fn elf_aux_info(key: usize) -> Result<usize, ()> {
+    use core::mem;
+    use libc;
+
+    let mut out: usize = 0;
+    let out_size = mem::size_of::<usize>();
+    let result = unsafe {
+        libc::elf_aux_info(
+            key as libc::c_int,
+            &mut out as *mut usize as *mut libc::c_void,
+            out_size as libc::c_int,
+        )
+    };
+
+    // elf_aux_info returns zero on success, and non-zero (errno) when the
+    // key is not recognized or not present in this process' auxiliary
+    // vector.
+    if result == 0 {
+        Ok(out)
+    } else {
+        Err(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auxv_dump() {
+        if let Ok(auxvec) = auxv() {
+            println!("{:?}", auxvec);
+        } else {
+            println!("elf_aux_info() failed to read the auxiliary vector!");
+        }
+    }
+}
@@ -0,0 +1,11 @@
+//! Run-time feature detection on FreeBSD.
+
+mod auxvec;
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64;
+
+pub(crate) use self::auxvec::auxv;
+
+#[cfg(target_arch = "aarch64")]
+pub(crate) use self::aarch64::AtHwcap;
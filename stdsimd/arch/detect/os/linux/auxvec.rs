@@ -8,8 +8,10 @@ use _std::io::Read;
 /// Key to access the CPU Hardware capabilities bitfield.
 pub const AT_HWCAP: usize = 16;
 /// Key to access the CPU Hardware capabilities 2 bitfield.
-#[cfg(any(target_arch = "arm", target_arch = "powerpc64"))]
+#[cfg(any(target_arch = "arm", target_arch = "aarch64", target_arch = "powerpc64"))]
 pub const AT_HWCAP2: usize = 26;
+/// Key marking the end of the auxiliary vector.
+const AT_NULL: usize = 0;
 
 /// Cache HWCAP bitfields of the ELF Auxiliary Vector.
 ///
@@ -18,7 +20,7 @@ pub const AT_HWCAP2: usize = 26;
 #[derive(Debug, Copy, Clone)]
 pub struct AuxVec {
     pub hwcap: usize,
-    #[cfg(any(target_arch = "arm", target_arch = "powerpc64"))]
+    #[cfg(any(target_arch = "arm", target_arch = "aarch64", target_arch = "powerpc64"))]
     pub hwcap2: usize,
 }
 
@@ -51,8 +53,8 @@ pub fn auxv() -> Result<AuxVec, ()> {
     // Try to call a dynamically-linked getauxval function.
     if let Ok(hwcap) = getauxval(AT_HWCAP) {
         // Targets with only AT_HWCAP:
-        #[cfg(any(target_arch = "aarch64", target_arch = "mips",
-                  target_arch = "mips64"))]
+        #[cfg(any(target_arch = "mips", target_arch = "mips64",
+                  target_arch = "riscv64"))]
         {
             if hwcap != 0 {
                 return Ok(AuxVec { hwcap });
@@ -68,6 +70,18 @@ pub fn auxv() -> Result<AuxVec, ()> {
                 }
             }
         }
+
+        // Aarch64 also has AT_HWCAP2, but it was only added to the kernel
+        // once SVE2 and friends needed a second word: treat it as optional
+        // and fall back to zero (all HWCAP2 features disabled) rather than
+        // failing the whole read when it is absent.
+        #[cfg(target_arch = "aarch64")]
+        {
+            if hwcap != 0 {
+                let hwcap2 = getauxval(AT_HWCAP2).unwrap_or(0);
+                return Ok(AuxVec { hwcap, hwcap2 });
+            }
+        }
     }
     // If calling getauxval fails, try to read the auxiliary vector from
     // its file:
@@ -77,6 +91,19 @@ pub fn auxv() -> Result<AuxVec, ()> {
 /// Tries to read the `key` from the auxiliary vector by calling the
 /// dynamically-linked `getauxval` function. If the function is not linked,
 /// this function return `Err`.
+///
+/// On `*-linux-gnu*` targets, glibc (>= 2.16) is guaranteed to export
+/// `getauxval` statically, so probing for it via `dlsym` is both wasted
+/// work and extra binary size. The `std_detect_dlsym_getauxval` feature
+/// opts back into the `dlsym` probe on those targets, e.g. for uses that
+/// need to run against an older glibc where the symbol may be absent; on
+/// every other target (musl and friends, where the symbol may simply not
+/// exist) the probe is always used.
+#[cfg(not(all(
+    target_os = "linux",
+    target_env = "gnu",
+    not(feature = "std_detect_dlsym_getauxval")
+)))]
 fn getauxval(key: usize) -> Result<usize, ()> {
     use libc;
     pub type F = unsafe extern "C" fn(usize) -> usize;
@@ -94,6 +121,19 @@ fn getauxval(key: usize) -> Result<usize, ()> {
     }
 }
 
+/// Calls the statically-linked `getauxval` directly, skipping the `dlsym`
+/// probe above. Only used on `*-linux-gnu*` targets, and only when the
+/// `std_detect_dlsym_getauxval` feature is disabled.
+#[cfg(all(
+    target_os = "linux",
+    target_env = "gnu",
+    not(feature = "std_detect_dlsym_getauxval")
+))]
+fn getauxval(key: usize) -> Result<usize, ()> {
+    use libc;
+    Ok(unsafe { libc::getauxval(key as libc::c_ulong) as usize })
+}
+
 /// Tries to read the auxiliary vector from the `file`. If this fails, this
 /// function returns `Err`.
 fn auxv_from_file(file: &str) -> Result<AuxVec, ()> {
@@ -105,23 +145,57 @@ fn auxv_from_file(file: &str) -> Result<AuxVec, ()> {
     // `AT_EXECFN = 31` to `AT_NULL = 0`. That is, a buffer of
     // 2*32 `usize` elements is enough to read the whole vector.
     let mut buf = [0_usize; 64];
-    {
+    let len_bytes = {
         let raw: &mut [u8; 64 * mem::size_of::<usize>()] =
             unsafe { mem::transmute(&mut buf) };
-        file.read(raw).map_err(|_| ())?;
+
+        // A single `read` can return fewer bytes than requested even when
+        // more data is available (this is common when `file` is a pipe, and
+        // has been observed for `/proc/self/auxv` as well), so keep reading
+        // until the buffer is full or we hit EOF, and only interpret the
+        // bytes that were actually read.
+        let mut read = 0;
+        loop {
+            match file.read(&mut raw[read..]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(ref e) if e.kind() == _std::io::ErrorKind::Interrupted => {
+                    continue
+                }
+                Err(_) => return Err(()),
+            }
+            if read == raw.len() {
+                break;
+            }
+        }
+        read
+    };
+
+    // A buffer that ends in the middle of a (key, value) pair is malformed:
+    // bail out rather than parse the trailing garbage as a key.
+    if len_bytes % (2 * mem::size_of::<usize>()) != 0 {
+        return Err(());
     }
-    auxv_from_buf(&buf)
+
+    auxv_from_buf(&buf, len_bytes / mem::size_of::<usize>())
 }
 
-/// Tries to interpret the `buffer` as an auxiliary vector. If that fails, this
-/// function returns `Err`.
-fn auxv_from_buf(buf: &[usize; 64]) -> Result<AuxVec, ()> {
+/// Tries to interpret the first `len` elements of `buffer` as an auxiliary
+/// vector. If this fails, or `len` ends in the middle of a (key, value)
+/// pair, this function returns `Err`.
+fn auxv_from_buf(buf: &[usize; 64], len: usize) -> Result<AuxVec, ()> {
+    if len % 2 != 0 {
+        return Err(());
+    }
+    let buf = &buf[..len];
+
     // Targets with only AT_HWCAP:
-    #[cfg(any(target_arch = "aarch64", target_arch = "mips",
-              target_arch = "mips64"))]
+    #[cfg(any(target_arch = "mips", target_arch = "mips64",
+              target_arch = "riscv64"))]
     {
         for el in buf.chunks(2) {
             match el[0] {
+                AT_NULL => break,
                 AT_HWCAP => return Ok(AuxVec { hwcap: el[1] }),
                 _ => (),
             }
@@ -134,6 +208,7 @@ fn auxv_from_buf(buf: &[usize; 64]) -> Result<AuxVec, ()> {
         let mut hwcap2 = None;
         for el in buf.chunks(2) {
             match el[0] {
+                AT_NULL => break,
                 AT_HWCAP => hwcap = Some(el[1]),
                 AT_HWCAP2 => hwcap2 = Some(el[1]),
                 _ => (),
@@ -144,6 +219,24 @@ fn auxv_from_buf(buf: &[usize; 64]) -> Result<AuxVec, ()> {
             return Ok(AuxVec { hwcap, hwcap2 });
         }
     }
+    // Aarch64: AT_HWCAP2 is optional, missing means all its bits are zero.
+    #[cfg(target_arch = "aarch64")]
+    {
+        let mut hwcap = None;
+        let mut hwcap2 = 0;
+        for el in buf.chunks(2) {
+            match el[0] {
+                AT_NULL => break,
+                AT_HWCAP => hwcap = Some(el[1]),
+                AT_HWCAP2 => hwcap2 = el[1],
+                _ => (),
+            }
+        }
+
+        if let Some(hwcap) = hwcap {
+            return Ok(AuxVec { hwcap, hwcap2 });
+        }
+    }
     Err(())
 }
 
@@ -240,6 +333,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn auxv_from_buf_rejects_trailing_partial_pair() {
+        // Three elements end in the middle of the second (key, value)
+        // pair: `auxv_from_buf` must return `Err` rather than interpret
+        // the dangling single element at the end as a key.
+        let mut buf = [0_usize; 64];
+        buf[0] = 0xffff_ffff;
+        buf[1] = 0x1111_1111;
+        buf[2] = 0xffff_fffe;
+        assert!(auxv_from_buf(&buf, 3).is_err());
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    #[test]
+    fn auxv_from_buf_ignores_entries_after_at_null() {
+        // `AT_NULL` marks the end of the vector: the `AT_HWCAP2` pair that
+        // follows it is garbage and must be ignored, leaving `hwcap2` at
+        // its default of zero.
+        let mut buf = [0_usize; 64];
+        buf[0] = AT_HWCAP;
+        buf[1] = 0x1234;
+        buf[2] = AT_NULL;
+        buf[3] = 0;
+        buf[4] = AT_HWCAP2;
+        buf[5] = 0xbeef;
+        let v = auxv_from_buf(&buf, 6).expect("AT_HWCAP was present");
+        assert_eq!(v.hwcap, 0x1234);
+        assert_eq!(v.hwcap2, 0);
+    }
+
     #[test]
     fn auxv_dump_procfs() {
         if let Ok(auxvec) = auxv_from_file("/proc/self/auxv") {
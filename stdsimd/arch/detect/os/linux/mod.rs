@@ -0,0 +1,17 @@
+//! Run-time feature detection on Linux.
+
+mod auxvec;
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64;
+
+#[cfg(target_arch = "riscv64")]
+mod riscv;
+
+pub(crate) use self::auxvec::auxv;
+
+#[cfg(target_arch = "aarch64")]
+pub(crate) use self::aarch64::AtHwcap;
+
+#[cfg(target_arch = "riscv64")]
+pub(crate) use self::riscv::detect_features;
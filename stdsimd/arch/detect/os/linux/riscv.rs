@@ -0,0 +1,130 @@
+//! Run-time feature detection for RISC-V on Linux.
+//!
+//! Detection happens in two stages:
+//!
+//! - `AT_HWCAP` gives a single word covering the base ISA extension letters
+//!   (one bit per letter, `bit = letter - 'a'`). This is always available.
+//! - The `riscv_hwprobe` syscall gives a richer, versioned view of the many
+//!   extensions that don't fit in a single HWCAP bit (the `Zb*` bit-
+//!   manipulation extensions, etc). It is only present on newer kernels, so
+//!   its result is layered on top of the HWCAP bits rather than replacing
+//!   them.
+
+use super::auxvec::{auxv, AT_HWCAP};
+use super::super::cache::Initializer;
+
+/// RISC-V CPU Feature enum. Each variant denotes a position in the feature
+/// bitset that `Initializer` understands.
+#[allow(non_camel_case_types)]
+#[repr(u32)]
+pub(crate) enum Feature {
+    a = 0,
+    c = 1,
+    d = 2,
+    f = 3,
+    m = 4,
+    v = 5,
+    zba = 6,
+    zbb = 7,
+    zbs = 8,
+}
+
+/// Detects the presence of each RISC-V extension via the auxiliary vector
+/// and, where available, the `riscv_hwprobe` syscall.
+pub(crate) fn detect_features() -> Initializer {
+    let mut value = Initializer::default();
+
+    if let Ok(auxv) = auxv() {
+        for (letter, feature) in &[
+            (b'a', Feature::a),
+            (b'c', Feature::c),
+            (b'd', Feature::d),
+            (b'f', Feature::f),
+            (b'm', Feature::m),
+            (b'v', Feature::v),
+        ] {
+            if hwcap_has_extension(auxv.hwcap, *letter) {
+                value.set(*feature as u32);
+            }
+        }
+    }
+
+    if let Some(ima_ext_0) = hwprobe_ima_ext_0() {
+        if ima_ext_0 & RISCV_HWPROBE_EXT_ZBA != 0 {
+            value.set(Feature::zba as u32);
+        }
+        if ima_ext_0 & RISCV_HWPROBE_EXT_ZBB != 0 {
+            value.set(Feature::zbb as u32);
+        }
+        if ima_ext_0 & RISCV_HWPROBE_EXT_ZBS != 0 {
+            value.set(Feature::zbs as u32);
+        }
+    }
+
+    value
+}
+
+/// Tests whether `hwcap` has the bit for the base extension `letter` set
+/// (bit `letter - 'a'`), e.g. `letter = b'a'` tests bit 0 (atomics).
+fn hwcap_has_extension(hwcap: usize, letter: u8) -> bool {
+    debug_assert!(letter.is_ascii_lowercase());
+    let bit = (letter - b'a') as u32;
+    hwcap & (1 << bit) != 0
+}
+
+// See Linux's `arch/riscv/include/uapi/asm/hwprobe.h`.
+const RISCV_HWPROBE_KEY_IMA_EXT_0: i64 = 4;
+const RISCV_HWPROBE_EXT_ZBA: u64 = 1 << 3;
+const RISCV_HWPROBE_EXT_ZBB: u64 = 1 << 4;
+const RISCV_HWPROBE_EXT_ZBS: u64 = 1 << 5;
+
+/// A single `{ key, value }` pair as understood by the `riscv_hwprobe`
+/// syscall.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+struct riscv_hwprobe {
+    key: i64,
+    value: u64,
+}
+
+/// Queries the `RISCV_HWPROBE_KEY_IMA_EXT_0` bitmask via the `riscv_hwprobe`
+/// syscall. Returns `None` if the syscall is unavailable (`ENOSYS`, e.g. on
+/// an older kernel) so callers can gracefully degrade to HWCAP-only
+/// detection.
+fn hwprobe_ima_ext_0() -> Option<u64> {
+    let mut pairs = [riscv_hwprobe {
+        key: RISCV_HWPROBE_KEY_IMA_EXT_0,
+        value: 0,
+    }];
+
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_riscv_hwprobe,
+            pairs.as_mut_ptr(),
+            pairs.len(),
+            0_usize, // cpu_count
+            core::ptr::null::<usize>(), // cpus
+            0_usize, // flags
+        )
+    };
+
+    if ret == 0 && pairs[0].key == RISCV_HWPROBE_KEY_IMA_EXT_0 {
+        Some(pairs[0].value)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_base_extension_letters() {
+        // bit 0 = 'a', bit 12 = 'm', bit 21 = 'v'
+        assert!(hwcap_has_extension(1 << 0, b'a'));
+        assert!(hwcap_has_extension(1 << 12, b'm'));
+        assert!(hwcap_has_extension(1 << 21, b'v'));
+        assert!(!hwcap_has_extension(1 << 21, b'a'));
+    }
+}
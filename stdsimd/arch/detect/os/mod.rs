@@ -0,0 +1,24 @@
+//! OS-specific run-time feature detection backends.
+
+cfg_if! {
+    if #[cfg(target_os = "linux")] {
+        mod linux;
+        pub(crate) use self::linux::auxv;
+        #[cfg(target_arch = "aarch64")]
+        pub(crate) use self::linux::AtHwcap;
+        #[cfg(target_arch = "riscv64")]
+        pub(crate) use self::linux::detect_features;
+    } else if #[cfg(target_os = "freebsd")] {
+        mod freebsd;
+        pub(crate) use self::freebsd::auxv;
+        #[cfg(target_arch = "aarch64")]
+        pub(crate) use self::freebsd::AtHwcap;
+    } else if #[cfg(all(target_os = "windows", target_arch = "aarch64"))] {
+        // Windows has no auxiliary vector; `windows::detect_features` goes
+        // straight to `cache::Initializer` instead of through `auxv`.
+        mod windows;
+        pub(crate) use self::windows::detect_features;
+    } else {
+        mod other;
+    }
+}
@@ -0,0 +1,8 @@
+//! Fallback for platforms without run-time feature detection support.
+
+use super::super::cache::Initializer;
+
+#[allow(dead_code)]
+pub(crate) fn detect_features() -> Initializer {
+    Initializer::default()
+}
@@ -0,0 +1,110 @@
+//! Run-time feature detection for Aarch64 on Windows.
+
+use super::super::aarch64::Feature;
+use super::super::cache::Initializer;
+
+/// Values of `PF_*` from `winnt.h` that we care about for Aarch64.
+///
+/// See [`IsProcessorFeaturePresent`][docs].
+///
+/// [docs]: https://docs.microsoft.com/en-us/windows/win32/api/processthreadsapi/nf-processthreadsapi-isprocessorfeaturepresent
+const PF_ARM_V8_CRYPTO_INSTRUCTIONS_AVAILABLE: u32 = 30;
+const PF_ARM_V8_CRC32_INSTRUCTIONS_AVAILABLE: u32 = 31;
+const PF_ARM_V81_ATOMIC_INSTRUCTIONS_AVAILABLE: u32 = 34;
+const PF_ARM_V82_DP_INSTRUCTIONS_AVAILABLE: u32 = 43;
+
+/// A decoded view of which `PF_ARM_*` feature groups Windows reports as
+/// present, with one named boolean per group instead of ad-hoc
+/// `is_processor_feature_present` calls scattered through the detection
+/// code. Kept separate from the `IsProcessorFeaturePresent` calls so the
+/// PF_* -> `Feature` mapping can be tested without the Win32 API.
+#[derive(Debug, Default, Copy, Clone)]
+struct AtPf {
+    crypto: bool,
+    crc32: bool,
+    atomics: bool,
+    dotprod: bool,
+}
+
+impl AtPf {
+    /// Queries the `PF_ARM_*` feature groups via `IsProcessorFeaturePresent`.
+    fn query() -> Self {
+        AtPf {
+            crypto: is_processor_feature_present(
+                PF_ARM_V8_CRYPTO_INSTRUCTIONS_AVAILABLE,
+            ),
+            crc32: is_processor_feature_present(
+                PF_ARM_V8_CRC32_INSTRUCTIONS_AVAILABLE,
+            ),
+            atomics: is_processor_feature_present(
+                PF_ARM_V81_ATOMIC_INSTRUCTIONS_AVAILABLE,
+            ),
+            dotprod: is_processor_feature_present(
+                PF_ARM_V82_DP_INSTRUCTIONS_AVAILABLE,
+            ),
+        }
+    }
+
+    /// Folds the decoded groups into the shared `cache::Initializer`.
+    ///
+    /// The bits it sets come from the same `Feature` enum the Linux
+    /// `AtHwcap` backend uses, so a given bit means the same feature on
+    /// both OSes.
+    fn into_initializer(self) -> Initializer {
+        let mut value = Initializer::default();
+
+        if self.crypto {
+            value.set(Feature::aes as u32);
+            value.set(Feature::pmull as u32);
+            value.set(Feature::sha1 as u32);
+            value.set(Feature::sha2 as u32);
+        }
+        if self.crc32 {
+            value.set(Feature::crc32 as u32);
+        }
+        if self.atomics {
+            value.set(Feature::atomics as u32);
+        }
+        if self.dotprod {
+            value.set(Feature::asimddp as u32);
+        }
+
+        value
+    }
+}
+
+/// Detects Aarch64 features on Windows by calling the Win32
+/// `IsProcessorFeaturePresent` API. This bypasses `auxv()` entirely: there is
+/// no auxiliary vector on Windows, and this API is the documented way of
+/// performing run-time feature detection there.
+pub(crate) fn detect_features() -> Initializer {
+    AtPf::query().into_initializer()
+}
+
+/// Thin wrapper around the `IsProcessorFeaturePresent` Win32 API.
+fn is_processor_feature_present(feature: u32) -> bool {
+    use winapi::um::processthreadsapi::IsProcessorFeaturePresent;
+    unsafe { IsProcessorFeaturePresent(feature) != 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crypto_group_sets_aes_pmull_sha1_sha2() {
+        let value = AtPf { crypto: true, ..AtPf::default() }.into_initializer();
+        assert!(value.test(Feature::aes as u32));
+        assert!(value.test(Feature::pmull as u32));
+        assert!(value.test(Feature::sha1 as u32));
+        assert!(value.test(Feature::sha2 as u32));
+        assert!(!value.test(Feature::crc32 as u32));
+    }
+
+    #[test]
+    fn dotprod_sets_asimddp() {
+        let value = AtPf { dotprod: true, ..AtPf::default() }.into_initializer();
+        assert!(value.test(Feature::asimddp as u32));
+        assert!(!value.test(Feature::atomics as u32));
+    }
+}
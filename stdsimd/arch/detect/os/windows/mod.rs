@@ -0,0 +1,7 @@
+//! Run-time feature detection on Windows.
+
+#[cfg(target_arch = "aarch64")]
+mod aarch64;
+
+#[cfg(target_arch = "aarch64")]
+pub(crate) use self::aarch64::detect_features;